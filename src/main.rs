@@ -6,30 +6,126 @@ use crossterm::{
 };
 use dirs::home_dir;
 use regex::Regex;
-use reqwest::Client;
+use reqwest::Client as HttpClient;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fs::File,
     io::{self, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Command as ProcessCommand, Stdio},
 };
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Message {
     role: String,
-    content: String,
+    // OpenAI-style APIs send an explicit JSON `null` here (not just omit the key) for an
+    // assistant message that only carries `tool_calls`, so this has to be `Option`, not a
+    // `String` with `#[serde(default)]` — `default` only covers a missing key, not `null`.
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<ToolCall>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+}
+
+impl Message {
+    fn user(content: impl Into<String>) -> Self {
+        Message {
+            role: "user".to_string(),
+            content: Some(content.into()),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Message {
+            role: "tool".to_string(),
+            content: Some(content.into()),
+            tool_calls: None,
+            tool_call_id: Some(tool_call_id.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ToolCall {
+    id: String,
+    #[serde(rename = "type")]
+    call_type: String,
+    function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    arguments: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Tool {
+    #[serde(rename = "type")]
+    tool_type: String,
+    function: FunctionDef,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<Message>,
+struct FunctionDef {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+fn execute_code_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: FunctionDef {
+            name: "execute_code".to_string(),
+            description: "Execute a shell or script snippet and return its captured output."
+                .to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "language": {
+                        "type": "string",
+                        "description": "The language tag of the snippet, e.g. bash, python, js."
+                    },
+                    "code": {
+                        "type": "string",
+                        "description": "The source code to execute."
+                    }
+                },
+                "required": ["language", "code"]
+            }),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
 }
 
 #[derive(Debug, Deserialize)]
 struct Choice {
     message: Message,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,6 +139,11 @@ struct CodeSnippet {
     code: String,
 }
 
+struct ExecutionOutcome {
+    output: String,
+    success: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Config {
     api_token: String,
@@ -50,6 +151,327 @@ struct Config {
     api_url: String,
     #[serde(default = "default_model")]
     default_model: String,
+    #[serde(default)]
+    provider: Provider,
+}
+
+/// Which backend `api_url`/`api_token` are interpreted against.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Provider {
+    #[default]
+    OpenAiCompatible,
+    AnthropicNative,
+    Ollama,
+}
+
+/// A fully-shaped HTTP request for a chat completion, before it's handed to reqwest.
+struct BuiltRequest {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: serde_json::Value,
+}
+
+/// Shapes a chat request/response for one backend's wire format. Implementors own their
+/// request body and headers entirely; `send_chat_request` only knows the uniform
+/// `Message`/`ChatResponse` types either side of it.
+trait Client {
+    fn build_request(
+        &self,
+        model: &str,
+        messages: &[Message],
+        tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> BuiltRequest;
+
+    fn parse_response(&self, body: &serde_json::Value) -> Result<ChatResponse, Box<dyn std::error::Error>>;
+}
+
+struct OpenAiCompatibleClient {
+    api_url: String,
+    api_token: String,
+}
+
+impl Client for OpenAiCompatibleClient {
+    fn build_request(
+        &self,
+        model: &str,
+        messages: &[Message],
+        tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> BuiltRequest {
+        let body = serde_json::json!({
+            "model": model,
+            "messages": messages,
+            "tools": tools,
+            "stream": stream,
+        });
+
+        BuiltRequest {
+            url: self.api_url.clone(),
+            headers: vec![
+                ("Content-Type", "application/json".to_string()),
+                ("Authorization", format!("Bearer {}", self.api_token)),
+            ],
+            body,
+        }
+    }
+
+    fn parse_response(&self, body: &serde_json::Value) -> Result<ChatResponse, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_value(body.clone())?)
+    }
+}
+
+/// Talks to Anthropic's native Messages API: system prompt pulled out of `messages` into
+/// its own field, `x-api-key` auth instead of a bearer token, and a content-block response.
+struct AnthropicNativeClient {
+    api_url: String,
+    api_token: String,
+}
+
+impl Client for AnthropicNativeClient {
+    fn build_request(
+        &self,
+        model: &str,
+        messages: &[Message],
+        tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> BuiltRequest {
+        let system = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_deref().unwrap_or(""))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let anthropic_messages: Vec<serde_json::Value> = messages
+            .iter()
+            .filter(|m| m.role != "system")
+            .map(|m| {
+                if m.role == "tool" {
+                    serde_json::json!({
+                        "role": "user",
+                        "content": [{
+                            "type": "tool_result",
+                            "tool_use_id": m.tool_call_id,
+                            "content": m.content.as_deref().unwrap_or(""),
+                        }],
+                    })
+                } else if let Some(calls) = &m.tool_calls {
+                    // Anthropic expects the prior turn's tool requests back as `tool_use`
+                    // content blocks, alongside any text, so the following `tool_result`
+                    // messages have a matching `tool_use_id` to point at.
+                    let mut blocks = Vec::new();
+                    if let Some(text) = m.content.as_deref().filter(|t| !t.is_empty()) {
+                        blocks.push(serde_json::json!({ "type": "text", "text": text }));
+                    }
+                    for call in calls {
+                        let input: serde_json::Value = serde_json::from_str(&call.function.arguments)
+                            .unwrap_or(serde_json::Value::Null);
+                        blocks.push(serde_json::json!({
+                            "type": "tool_use",
+                            "id": call.id,
+                            "name": call.function.name,
+                            "input": input,
+                        }));
+                    }
+                    serde_json::json!({ "role": m.role, "content": blocks })
+                } else {
+                    serde_json::json!({ "role": m.role, "content": m.content.as_deref().unwrap_or("") })
+                }
+            })
+            .collect();
+
+        let mut body = serde_json::json!({
+            "model": model,
+            "max_tokens": 4096,
+            "messages": anthropic_messages,
+            "stream": stream,
+        });
+        if !system.is_empty() {
+            body["system"] = serde_json::Value::String(system);
+        }
+        if let Some(tools) = tools {
+            body["tools"] = serde_json::Value::Array(
+                tools
+                    .iter()
+                    .map(|tool| {
+                        serde_json::json!({
+                            "name": tool.function.name,
+                            "description": tool.function.description,
+                            "input_schema": tool.function.parameters,
+                        })
+                    })
+                    .collect(),
+            );
+        }
+
+        BuiltRequest {
+            url: self.api_url.clone(),
+            headers: vec![
+                ("Content-Type", "application/json".to_string()),
+                ("x-api-key", self.api_token.clone()),
+                ("anthropic-version", "2023-06-01".to_string()),
+            ],
+            body,
+        }
+    }
+
+    fn parse_response(&self, body: &serde_json::Value) -> Result<ChatResponse, Box<dyn std::error::Error>> {
+        let blocks = body["content"].as_array().cloned().unwrap_or_default();
+
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for block in &blocks {
+            match block["type"].as_str() {
+                Some("text") => {
+                    if let Some(t) = block["text"].as_str() {
+                        text.push_str(t);
+                    }
+                }
+                Some("tool_use") => {
+                    tool_calls.push(ToolCall {
+                        id: block["id"].as_str().unwrap_or_default().to_string(),
+                        call_type: "function".to_string(),
+                        function: FunctionCall {
+                            name: block["name"].as_str().unwrap_or_default().to_string(),
+                            arguments: block["input"].to_string(),
+                        },
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Anthropic's `tool_use` stop reason is this backend's equivalent of the
+        // OpenAI-compatible backend's `tool_calls`; normalize so the shared agent loop in
+        // `main` doesn't need to know which backend it's talking to.
+        let finish_reason = match body["stop_reason"].as_str() {
+            Some("tool_use") => Some("tool_calls".to_string()),
+            other => other.map(|s| s.to_string()),
+        };
+
+        Ok(ChatResponse {
+            choices: vec![Choice {
+                message: Message {
+                    role: "assistant".to_string(),
+                    content: if text.is_empty() { None } else { Some(text) },
+                    tool_calls: if tool_calls.is_empty() {
+                        None
+                    } else {
+                        Some(tool_calls)
+                    },
+                    tool_call_id: None,
+                },
+                finish_reason,
+            }],
+        })
+    }
+}
+
+/// Talks to a local Ollama daemon: no auth, and the response is the message object
+/// directly rather than a `choices` array.
+struct OllamaClient {
+    api_url: String,
+}
+
+impl Client for OllamaClient {
+    fn build_request(
+        &self,
+        model: &str,
+        messages: &[Message],
+        _tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> BuiltRequest {
+        let body = serde_json::json!({
+            "model": model,
+            "messages": messages,
+            "stream": stream,
+        });
+
+        BuiltRequest {
+            url: self.api_url.clone(),
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body,
+        }
+    }
+
+    fn parse_response(&self, body: &serde_json::Value) -> Result<ChatResponse, Box<dyn std::error::Error>> {
+        let message: Message = serde_json::from_value(body["message"].clone())?;
+        let finish_reason = if body["done"].as_bool().unwrap_or(false) {
+            Some("stop".to_string())
+        } else {
+            None
+        };
+
+        Ok(ChatResponse {
+            choices: vec![Choice {
+                message,
+                finish_reason,
+            }],
+        })
+    }
+}
+
+/// Dispatches to whichever backend `Config::provider` selects.
+enum Backend {
+    OpenAiCompatible(OpenAiCompatibleClient),
+    AnthropicNative(AnthropicNativeClient),
+    Ollama(OllamaClient),
+}
+
+impl Backend {
+    fn from_config(config: &Config) -> Self {
+        // Respect a custom `api_url`; otherwise fall back to each provider's own default
+        // rather than the generic ppq.ai one.
+        let using_default_url = config.api_url == default_api_url();
+
+        match config.provider {
+            Provider::OpenAiCompatible => Backend::OpenAiCompatible(OpenAiCompatibleClient {
+                api_url: config.api_url.clone(),
+                api_token: config.api_token.clone(),
+            }),
+            Provider::AnthropicNative => Backend::AnthropicNative(AnthropicNativeClient {
+                api_url: if using_default_url {
+                    "https://api.anthropic.com/v1/messages".to_string()
+                } else {
+                    config.api_url.clone()
+                },
+                api_token: config.api_token.clone(),
+            }),
+            Provider::Ollama => Backend::Ollama(OllamaClient {
+                api_url: if using_default_url {
+                    "http://localhost:11434/api/chat".to_string()
+                } else {
+                    config.api_url.clone()
+                },
+            }),
+        }
+    }
+}
+
+impl Client for Backend {
+    fn build_request(
+        &self,
+        model: &str,
+        messages: &[Message],
+        tools: Option<&[Tool]>,
+        stream: bool,
+    ) -> BuiltRequest {
+        match self {
+            Backend::OpenAiCompatible(client) => client.build_request(model, messages, tools, stream),
+            Backend::AnthropicNative(client) => client.build_request(model, messages, tools, stream),
+            Backend::Ollama(client) => client.build_request(model, messages, tools, stream),
+        }
+    }
+
+    fn parse_response(&self, body: &serde_json::Value) -> Result<ChatResponse, Box<dyn std::error::Error>> {
+        match self {
+            Backend::OpenAiCompatible(client) => client.parse_response(body),
+            Backend::AnthropicNative(client) => client.parse_response(body),
+            Backend::Ollama(client) => client.parse_response(body),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -124,6 +546,222 @@ const AVAILABLE_MODELS: [&str; 20] = [
 ];
 const DEFAULT_MODEL: &str = "claude-3.7-sonnet";
 
+/// A language backed by a `~/.ppq/plugins/` executable rather than a built-in interpreter.
+#[derive(Debug, Clone)]
+struct PluginLanguage {
+    name: String,
+    markdown_tags: Vec<String>,
+    executable: PathBuf,
+}
+
+/// A language resolved from either the built-in table or the plugin registry.
+enum Language {
+    Builtin(&'static SupportedLanguage),
+    Plugin(PluginLanguage),
+}
+
+impl Language {
+    fn name(&self) -> &str {
+        match self {
+            Language::Builtin(lang) => lang.name,
+            Language::Plugin(plugin) => &plugin.name,
+        }
+    }
+}
+
+/// The combined set of executable languages: the built-in `SUPPORTED_LANGUAGES` table plus
+/// whatever plugins were discovered in `~/.ppq/plugins/` at startup.
+struct LanguageRegistry {
+    plugins: Vec<PluginLanguage>,
+}
+
+impl LanguageRegistry {
+    /// Scans `~/.ppq/plugins/` for executables and `describe`s each one over JSON-RPC.
+    /// Plugins that fail to describe are skipped with a warning rather than aborting startup.
+    fn discover() -> Self {
+        let mut plugins = Vec::new();
+
+        let dir = match plugins_dir() {
+            Ok(dir) => dir,
+            Err(_) => return LanguageRegistry { plugins },
+        };
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => return LanguageRegistry { plugins },
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !is_executable_file(&path) {
+                continue;
+            }
+
+            match describe_plugin(&path) {
+                Ok(plugin) => plugins.push(plugin),
+                Err(e) => eprintln!(
+                    "{}",
+                    format!("Skipping plugin {}: {}", path.display(), e).yellow()
+                ),
+            }
+        }
+
+        LanguageRegistry { plugins }
+    }
+
+    fn find(&self, tag: &str) -> Result<Language, String> {
+        if let Some(builtin) = SUPPORTED_LANGUAGES
+            .iter()
+            .find(|lang| lang.markdown_tags.contains(&tag))
+        {
+            return Ok(Language::Builtin(builtin));
+        }
+
+        self.plugins
+            .iter()
+            .find(|plugin| plugin.markdown_tags.iter().any(|t| t.as_str() == tag))
+            .cloned()
+            .map(Language::Plugin)
+            .ok_or_else(|| format!("Unsupported language: {}", tag))
+    }
+}
+
+fn plugins_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut dir = home_dir().ok_or("Could not find home directory")?;
+    dir.push(".ppq");
+    dir.push("plugins");
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn is_executable_file(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.is_file()
+        && std::fs::metadata(path)
+            .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable_file(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[derive(Serialize)]
+struct RpcRequest<P: Serialize> {
+    jsonrpc: &'static str,
+    id: u32,
+    method: &'static str,
+    params: P,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<R> {
+    // `Option<R>` already deserializes a missing key to `None` on its own; a field-level
+    // `#[serde(default)]` here would additionally require `R: Default`, which plugin result
+    // types like `DescribeResult`/`ExecuteResult` don't implement.
+    result: Option<R>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct DescribeResult {
+    name: String,
+    markdown_tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ExecuteParams<'a> {
+    code: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ExecuteResult {
+    stdout: String,
+    stderr: String,
+    exit_code: i32,
+}
+
+/// Spawns `executable` with piped stdin/stdout, sends a single JSON-RPC request, and parses
+/// the first line of its response. Plugins are one-shot processes: a fresh one is spawned
+/// per call rather than kept alive between `describe` and `execute`.
+fn send_rpc_request<P, R>(
+    executable: &Path,
+    method: &'static str,
+    params: P,
+) -> Result<R, Box<dyn std::error::Error>>
+where
+    P: Serialize,
+    R: serde::de::DeserializeOwned,
+{
+    let mut child = ProcessCommand::new(executable)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let request = RpcRequest {
+        jsonrpc: "2.0",
+        id: 1,
+        method,
+        params,
+    };
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or("Could not open plugin's standard input")?;
+    writeln!(stdin, "{}", serde_json::to_string(&request)?)?;
+    drop(stdin);
+
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout
+        .lines()
+        .next()
+        .ok_or("Plugin produced no JSON-RPC response")?;
+
+    let response: RpcResponse<R> = serde_json::from_str(line)?;
+    response.result.ok_or_else(|| {
+        response
+            .error
+            .map(|e| e.message)
+            .unwrap_or_else(|| "Plugin returned no result".to_string())
+            .into()
+    })
+}
+
+fn describe_plugin(path: &Path) -> Result<PluginLanguage, Box<dyn std::error::Error>> {
+    let result: DescribeResult = send_rpc_request(path, "describe", serde_json::json!({}))?;
+    Ok(PluginLanguage {
+        name: result.name,
+        markdown_tags: result.markdown_tags,
+        executable: path.to_path_buf(),
+    })
+}
+
+fn execute_plugin(
+    plugin: &PluginLanguage,
+    code: &str,
+) -> Result<ExecutionOutcome, Box<dyn std::error::Error>> {
+    let result: ExecuteResult =
+        send_rpc_request(&plugin.executable, "execute", ExecuteParams { code })?;
+
+    let mut combined = result.stdout;
+    combined.push_str(&result.stderr);
+
+    Ok(ExecutionOutcome {
+        output: combined,
+        success: result.exit_code == 0,
+    })
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let matches = Command::new("ppq-assistant")
@@ -134,9 +772,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .required(false)
                 .default_value(DEFAULT_MODEL),
         )
-        .arg(Arg::new("prompt").num_args(1..).required(true))
+        .arg(
+            Arg::new("interactive")
+                .long("interactive")
+                .short('i')
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("stream")
+                .long("stream")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("interactive"),
+        )
+        .arg(
+            Arg::new("prompt")
+                .num_args(1..)
+                .required_unless_present("interactive"),
+        )
         .get_matches();
 
+    // Read config file
+    let config = read_config()?;
+
+    // Get the model from arguments or use config default
+    let model = matches
+        .get_one::<String>("model")
+        .cloned()
+        .unwrap_or_else(|| config.default_model.clone());
+
+    let registry = LanguageRegistry::discover();
+    let backend = Backend::from_config(&config);
+
+    if matches.get_flag("interactive") {
+        return run_interactive(&backend, model, &registry).await;
+    }
+
     // Parse arguments to extract prompt
     let mut prompt_parts: Vec<String> = Vec::new();
     let mut reached_delimiter = false;
@@ -160,39 +830,239 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Read config file
-    let config = read_config()?;
+    let streaming = matches.get_flag("stream");
 
-    // Get the model from arguments or use config default
-    let model = matches
-        .get_one::<String>("model")
-        .cloned()
-        .unwrap_or_else(|| config.default_model.clone());
+    let response = if streaming {
+        let text =
+            send_chat_request_streaming(&backend, &model, &[Message::user(prompt)]).await?;
+        println!();
+        text
+    } else {
+        // Run the tool-calling loop until the model settles on a plain answer
+        let mut messages = vec![Message::user(prompt)];
+        loop {
+            let chat_response = send_chat_request(&backend, &model, &messages).await?;
+            let choice = chat_response
+                .choices
+                .into_iter()
+                .next()
+                .ok_or("API response contained no choices")?;
+
+            if choice.finish_reason.as_deref() == Some("tool_calls") {
+                let tool_calls = choice.message.tool_calls.clone().unwrap_or_default();
+                messages.push(choice.message);
 
-    // Make the API request with config values
-    let response = send_request_async(&config.api_token, &model, &prompt).await?;
+                for call in tool_calls {
+                    let result_content = run_requested_tool_call(&call, &registry)?;
+                    messages.push(Message::tool(call.id, result_content));
+                }
+                continue;
+            }
+
+            break choice.message.content.unwrap_or_default();
+        }
+    };
 
     // Extract code snippets from the response
-    let snippets = extract_code_snippets(&response);
+    let snippets = extract_code_snippets(&response, &registry);
 
     if snippets.is_empty() {
-        println!("{}", response);
+        if !streaming {
+            println!("{}", response);
+        }
 
         println!("\n{}", "No executable code snippets found.".yellow());
         return Ok(());
     }
 
-    // Display the full response first
-    println!("{}", response);
+    // Display the full response first (already shown incrementally if streamed)
+    if !streaming {
+        println!("{}", response);
+    }
 
     // Display and allow selection of code snippets
-    if let Some(selected_snippet) = select_snippet(&snippets)? {
-        execute_snippet(&selected_snippet)?;
+    if let Some(selected_snippet) = select_snippet(&snippets, &registry)? {
+        run_selected_snippet(&selected_snippet, &registry)?;
     }
 
     Ok(())
 }
 
+/// Fills in any `<name>`/`<name: default>`/`{{name}}` placeholders in a user-selected
+/// snippet, shows the finalized code for confirmation, and executes it.
+fn run_selected_snippet(
+    snippet: &CodeSnippet,
+    registry: &LanguageRegistry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let finalized_code = substitute_placeholders(&snippet.code)?;
+    let snippet = CodeSnippet {
+        language: snippet.language.clone(),
+        code: finalized_code,
+    };
+
+    if !confirm_execution(&snippet)? {
+        return Ok(());
+    }
+
+    let outcome = execute_snippet(&snippet, registry)?;
+    print!("{}", outcome.output);
+    println!(
+        "\n{}\n",
+        if outcome.success {
+            "Execution completed successfully.".green().bold()
+        } else {
+            "Execution failed.".red().bold()
+        }
+    );
+
+    Ok(())
+}
+
+/// Builds the regex matching `<name>`, `<name: default>` and `{{name}}` placeholders:
+/// group 1 is an angle-bracket name, group 2 its optional default, group 3 a brace name.
+fn placeholder_regex() -> Regex {
+    Regex::new(r"<([A-Za-z_][A-Za-z0-9_]*)(?:\s*:\s*([^>]*))?>|\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}")
+        .unwrap()
+}
+
+/// Scans `code` for `<name>`/`<name: default>`/`{{name}}` placeholders and returns their
+/// distinct names in first-seen order alongside each name's default (empty if none given).
+fn find_placeholders(code: &str) -> (Vec<String>, HashMap<String, String>) {
+    let placeholder_regex = placeholder_regex();
+
+    let mut order: Vec<String> = Vec::new();
+    let mut defaults: HashMap<String, String> = HashMap::new();
+
+    for cap in placeholder_regex.captures_iter(code) {
+        let name = cap
+            .get(1)
+            .or_else(|| cap.get(3))
+            .map(|m| m.as_str().to_string())
+            .expect("placeholder regex always captures a name");
+        let default = cap.get(2).map_or(String::new(), |m| m.as_str().trim().to_string());
+
+        defaults.entry(name.clone()).or_insert_with(|| {
+            order.push(name);
+            default
+        });
+    }
+
+    (order, defaults)
+}
+
+/// Replaces every placeholder occurrence in `code` with its resolved value from `values`,
+/// keyed by placeholder name (as returned by `find_placeholders`).
+fn apply_placeholders(code: &str, values: &HashMap<String, String>) -> String {
+    placeholder_regex()
+        .replace_all(code, |caps: &regex::Captures| {
+            let name = caps.get(1).or_else(|| caps.get(3)).unwrap().as_str();
+            values[name].clone()
+        })
+        .into_owned()
+}
+
+/// Scans `code` for `<name>`, `<name: default>` and `{{name}}` placeholders, prompts the
+/// user once per distinct name (reusing the answer for every repeated occurrence), and
+/// substitutes the answers back in.
+fn substitute_placeholders(code: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let (order, defaults) = find_placeholders(code);
+
+    if order.is_empty() {
+        return Ok(code.to_string());
+    }
+
+    println!("\n{}", "This snippet has parameters to fill in:".green().bold());
+
+    let mut values: HashMap<String, String> = HashMap::new();
+    for name in &order {
+        let default = &defaults[name];
+        if default.is_empty() {
+            print!("{}: ", name);
+        } else {
+            print!("{} [{}]: ", name, default);
+        }
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        let answer = answer.trim();
+        let value = if answer.is_empty() {
+            default.clone()
+        } else {
+            answer.to_string()
+        };
+        values.insert(name.clone(), value);
+    }
+
+    let finalized = apply_placeholders(code, &values);
+
+    println!("\n{}", "Finalized snippet:".green().bold());
+    println!("{}\n", finalized);
+
+    Ok(finalized)
+}
+
+/// Asks the user to confirm a finalized snippet before it runs.
+fn confirm_execution(snippet: &CodeSnippet) -> Result<bool, Box<dyn std::error::Error>> {
+    print!("Execute this {} snippet? [y/N] ", snippet.language);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Confirms with the user, then runs the snippet requested by a single tool call,
+/// returning the text to feed back to the model as the tool's result.
+fn run_requested_tool_call(
+    call: &ToolCall,
+    registry: &LanguageRegistry,
+) -> Result<String, Box<dyn std::error::Error>> {
+    #[derive(Deserialize)]
+    struct ExecuteCodeArgs {
+        language: String,
+        code: String,
+    }
+
+    if call.function.name != "execute_code" {
+        return Ok(format!("Unknown tool requested: {}", call.function.name));
+    }
+
+    let args: ExecuteCodeArgs = serde_json::from_str(&call.function.arguments)
+        .map_err(|e| format!("Could not parse tool call arguments: {}", e))?;
+    let snippet = CodeSnippet {
+        language: args.language,
+        code: args.code,
+    };
+
+    if !is_executable(&snippet.language, registry) {
+        return Ok(format!("Unsupported language: {}", snippet.language));
+    }
+
+    if !confirm_tool_execution(&snippet)? {
+        return Ok("User declined to run this snippet.".to_string());
+    }
+
+    let outcome = execute_snippet(&snippet, registry)?;
+    print!("{}", outcome.output);
+    Ok(outcome.output)
+}
+
+/// Asks the user to approve a snippet the model wants to execute.
+fn confirm_tool_execution(snippet: &CodeSnippet) -> Result<bool, Box<dyn std::error::Error>> {
+    println!(
+        "\n{}",
+        "Model wants to execute the following snippet:".green().bold()
+    );
+    println!("{}\n", snippet.code);
+    print!("Allow execution? [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
 fn read_config() -> Result<Config, Box<dyn std::error::Error>> {
     let config_path = get_config_path()?;
 
@@ -217,34 +1087,212 @@ fn get_config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
     Ok(config_path)
 }
 
-async fn send_request_async(
-    api_token: &str,
+fn get_history_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut history_path = home_dir().ok_or("Could not find home directory")?;
+    history_path.push(".ppq");
+    history_path.push("history");
+    Ok(history_path)
+}
+
+/// Drops into a persistent REPL: conversation history carries across turns, replies still
+/// flow through the snippet-selection pipeline, and a handful of `:` meta-commands manage
+/// the session itself.
+async fn run_interactive(
+    backend: &Backend,
+    mut model: String,
+    registry: &LanguageRegistry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let history_path = get_history_path()?;
+    let mut rl = DefaultEditor::new()?;
+    let _ = rl.load_history(&history_path);
+
+    println!(
+        "{}",
+        "Entering interactive mode. :model <name>, :reset, :quit."
+            .green()
+            .bold()
+    );
+
+    let mut messages: Vec<Message> = Vec::new();
+
+    loop {
+        match rl.readline(&format!("{} ", "ppq>".cyan().bold())) {
+            Ok(line) => {
+                let input = line.trim();
+                if input.is_empty() {
+                    continue;
+                }
+                let _ = rl.add_history_entry(input);
+
+                if input == ":quit" {
+                    break;
+                }
+
+                if input == ":reset" {
+                    messages.clear();
+                    println!("{}", "Conversation history cleared.".green());
+                    continue;
+                }
+
+                if let Some(requested_model) = input.strip_prefix(":model ") {
+                    let requested_model = requested_model.trim();
+                    if AVAILABLE_MODELS.contains(&requested_model) {
+                        model = requested_model.to_string();
+                        println!("Switched to model {}", model.yellow());
+                    } else {
+                        println!("{}", format!("Unknown model: {}", requested_model).red());
+                    }
+                    continue;
+                }
+
+                messages.push(Message::user(input));
+
+                // Mirror the one-shot mode's tool-calling loop: keep feeding tool results
+                // back until the model settles on a plain answer, rather than leaving a
+                // dangling tool_calls message that the next turn's history would choke on.
+                let response = loop {
+                    let chat_response = send_chat_request(backend, &model, &messages).await?;
+                    let choice = chat_response
+                        .choices
+                        .into_iter()
+                        .next()
+                        .ok_or("API response contained no choices")?;
+
+                    if choice.finish_reason.as_deref() == Some("tool_calls") {
+                        let tool_calls = choice.message.tool_calls.clone().unwrap_or_default();
+                        messages.push(choice.message);
+
+                        for call in tool_calls {
+                            let result_content = run_requested_tool_call(&call, registry)?;
+                            messages.push(Message::tool(call.id, result_content));
+                        }
+                        continue;
+                    }
+
+                    let response = choice.message.content.clone().unwrap_or_default();
+                    messages.push(choice.message);
+                    break response;
+                };
+
+                println!("{}", response);
+
+                let snippets = extract_code_snippets(&response, registry);
+                if !snippets.is_empty() {
+                    if let Some(selected_snippet) = select_snippet(&snippets, registry)? {
+                        run_selected_snippet(&selected_snippet, registry)?;
+                    }
+                }
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                break;
+            }
+        }
+    }
+
+    let _ = rl.save_history(&history_path);
+    Ok(())
+}
+
+/// Thin dispatcher: builds the request through whichever `Backend` the config selected,
+/// sends it, and hands the raw JSON body back to that same backend to interpret.
+async fn send_chat_request(
+    backend: &Backend,
     model: &str,
-    prompt: &str,
+    messages: &[Message],
+) -> Result<ChatResponse, Box<dyn std::error::Error>> {
+    let built = backend.build_request(model, messages, Some(&[execute_code_tool()]), false);
+
+    let http = HttpClient::new();
+    let mut request = http.post(&built.url).json(&built.body);
+    for (name, value) in &built.headers {
+        request = request.header(*name, value);
+    }
+
+    let body: serde_json::Value = request.send().await?.json().await?;
+    backend.parse_response(&body)
+}
+
+/// Requests the chat completion as a server-sent-events stream, printing each content
+/// fragment as it arrives and returning the accumulated text once the stream ends.
+/// Only the OpenAI-compatible backend's SSE shape is understood here; other backends fall
+/// back to the buffered request and print the answer once it arrives, so `--stream` stays
+/// a best-effort speedup rather than a hard requirement on provider.
+async fn send_chat_request_streaming(
+    backend: &Backend,
+    model: &str,
+    messages: &[Message],
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let config = read_config()?;
-    let client = Client::new();
-    let request = ChatRequest {
-        model: model.to_string(),
-        messages: vec![Message {
-            role: "user".to_string(),
-            content: prompt.to_string(),
-        }],
+    let Backend::OpenAiCompatible(_) = backend else {
+        // No tools, same as the streaming path below — this is just the non-streaming
+        // request/response shape standing in for a format this function can't drain as SSE.
+        let built = backend.build_request(model, messages, None, false);
+
+        let http = HttpClient::new();
+        let mut request = http.post(&built.url).json(&built.body);
+        for (name, value) in &built.headers {
+            request = request.header(*name, value);
+        }
+        let body: serde_json::Value = request.send().await?.json().await?;
+        let chat_response = backend.parse_response(&body)?;
+        let text = chat_response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .unwrap_or_default();
+        print!("{}", text);
+        io::stdout().flush()?;
+        return Ok(text);
     };
 
-    let response = client
-        .post(&config.api_url)
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_token))
-        .json(&request)
-        .send()
-        .await?;
+    let built = backend.build_request(model, messages, None, true);
+
+    let http = HttpClient::new();
+    let mut request = http.post(&built.url).json(&built.body);
+    for (name, value) in &built.headers {
+        request = request.header(*name, value);
+    }
+
+    let mut response = request.send().await?;
+
+    let mut accumulated = String::new();
+    let mut buffer = String::new();
+
+    while let Some(chunk) = response.chunk().await? {
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(newline_pos) = buffer.find('\n') {
+            let line = buffer[..newline_pos].trim_end_matches('\r').to_string();
+            buffer.drain(..=newline_pos);
+
+            let Some(data) = line.strip_prefix("data: ") else {
+                continue;
+            };
+
+            if data == "[DONE]" {
+                return Ok(accumulated);
+            }
+
+            if let Ok(parsed) = serde_json::from_str::<StreamChunk>(data) {
+                if let Some(content) = parsed
+                    .choices
+                    .first()
+                    .and_then(|choice| choice.delta.content.as_deref())
+                {
+                    print!("{}", content);
+                    io::stdout().flush()?;
+                    accumulated.push_str(content);
+                }
+            }
+        }
+    }
 
-    let chat_response: ChatResponse = response.json().await?;
-    Ok(chat_response.choices[0].message.content.clone())
+    Ok(accumulated)
 }
 
-fn extract_code_snippets(markdown: &str) -> Vec<CodeSnippet> {
+fn extract_code_snippets(markdown: &str, registry: &LanguageRegistry) -> Vec<CodeSnippet> {
     let code_block_regex = Regex::new(r"```(\w+)?\s*\n([\s\S]*?)\n```").unwrap();
     let mut snippets = Vec::new();
 
@@ -253,7 +1301,7 @@ fn extract_code_snippets(markdown: &str) -> Vec<CodeSnippet> {
         let code = cap.get(2).map_or("", |m| m.as_str()).to_string();
 
         // Only include executable snippets
-        if is_executable(&language) {
+        if is_executable(&language, registry) {
             snippets.push(CodeSnippet { language, code });
         }
     }
@@ -261,12 +1309,13 @@ fn extract_code_snippets(markdown: &str) -> Vec<CodeSnippet> {
     snippets
 }
 
-fn is_executable(language: &str) -> bool {
-    find_language(language).is_ok()
+fn is_executable(language: &str, registry: &LanguageRegistry) -> bool {
+    registry.find(language).is_ok()
 }
 
 fn select_snippet(
     snippets: &[CodeSnippet],
+    registry: &LanguageRegistry,
 ) -> Result<Option<CodeSnippet>, Box<dyn std::error::Error>> {
     // Only show the last 10 snippets if there are more than 10
     let display_snippets = if snippets.len() > 10 {
@@ -279,12 +1328,13 @@ fn select_snippet(
 
     // Display the snippets with their indices
     for (i, snippet) in display_snippets.iter().enumerate() {
-        let language =
-            find_language(&snippet.language).expect("only supported languages are displayed");
+        let language = registry
+            .find(&snippet.language)
+            .expect("only supported languages are displayed");
         println!(
             "{}: {} snippet ({} lines)",
             i.to_string().cyan().bold(),
-            language.name.yellow().bold(),
+            language.name().yellow().bold(),
             snippet.code.lines().count()
         );
         // Preview first n lines
@@ -357,49 +1407,46 @@ fn select_snippet(
     Ok(result)
 }
 
-fn find_language(language: &str) -> Result<&'static SupportedLanguage, String> {
-    SUPPORTED_LANGUAGES
-        .iter()
-        .find(|lang| lang.markdown_tags.contains(&language))
-        .ok_or_else(|| format!("Unsupported language: {}", language))
-}
-
-fn execute_snippet(snippet: &CodeSnippet) -> Result<(), Box<dyn std::error::Error>> {
-    let lang = find_language(&snippet.language)?;
+/// Runs the snippet to completion, capturing stdout/stderr instead of inheriting the
+/// terminal, so the combined output can be shown to the user or fed back to the model.
+/// Dispatches to a built-in interpreter or, for plugin-backed languages, to the plugin's
+/// JSON-RPC `execute` call.
+fn execute_snippet(
+    snippet: &CodeSnippet,
+    registry: &LanguageRegistry,
+) -> Result<ExecutionOutcome, Box<dyn std::error::Error>> {
+    let lang = registry.find(&snippet.language)?;
 
     println!(
         "\n{}\n",
-        format!("Executing {} snippet...", lang.name).green().bold()
+        format!("Executing {} snippet...", lang.name()).green().bold()
     );
 
-    let output = ProcessCommand::new(lang.interpreter)
-        .args(
-            &lang
-                .interpreter_flags
-                .iter()
-                .copied()
-                .chain(vec![snippet.code.as_str()])
-                .collect::<Vec<&str>>(),
-        )
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .output()?;
+    match lang {
+        Language::Builtin(builtin) => {
+            let output = ProcessCommand::new(builtin.interpreter)
+                .args(
+                    builtin
+                        .interpreter_flags
+                        .iter()
+                        .copied()
+                        .chain(vec![snippet.code.as_str()])
+                        .collect::<Vec<&str>>(),
+                )
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .output()?;
 
-    println!(
-        "\n{}\n",
-        if output.status.success() {
-            "Execution completed successfully.".green().bold()
-        } else {
-            format!(
-                "Execution failed with status: {}",
-                output.status.code().unwrap_or(-1)
-            )
-            .red()
-            .bold()
-        }
-    );
+            let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+            combined.push_str(&String::from_utf8_lossy(&output.stderr));
 
-    Ok(())
+            Ok(ExecutionOutcome {
+                output: combined,
+                success: output.status.success(),
+            })
+        }
+        Language::Plugin(plugin) => execute_plugin(&plugin, &snippet.code),
+    }
 }
 
 fn default_api_url() -> String {
@@ -409,3 +1456,48 @@ fn default_api_url() -> String {
 fn default_model() -> String {
     DEFAULT_MODEL.to_string()
 }
+
+#[cfg(test)]
+mod placeholder_tests {
+    use super::*;
+
+    #[test]
+    fn finds_angle_bracket_placeholder_with_no_default() {
+        let (order, defaults) = find_placeholders("echo <name>");
+        assert_eq!(order, vec!["name".to_string()]);
+        assert_eq!(defaults["name"], "");
+    }
+
+    #[test]
+    fn finds_angle_bracket_placeholder_with_default() {
+        let (order, defaults) = find_placeholders("curl <host: localhost>");
+        assert_eq!(order, vec!["host".to_string()]);
+        assert_eq!(defaults["host"], "localhost");
+    }
+
+    #[test]
+    fn finds_double_brace_placeholder() {
+        let (order, defaults) = find_placeholders("echo {{ name }}");
+        assert_eq!(order, vec!["name".to_string()]);
+        assert_eq!(defaults["name"], "");
+    }
+
+    #[test]
+    fn reuses_one_answer_for_repeated_placeholders() {
+        let (order, _) = find_placeholders("echo <name>, hello <name>!");
+        assert_eq!(order, vec!["name".to_string()]);
+
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), "Ada".to_string());
+        let finalized = apply_placeholders("echo <name>, hello <name>!", &values);
+        assert_eq!(finalized, "echo Ada, hello Ada!");
+    }
+
+    #[test]
+    fn no_placeholders_is_a_no_op() {
+        let code = "echo hello world";
+        let (order, _) = find_placeholders(code);
+        assert!(order.is_empty());
+        assert_eq!(substitute_placeholders(code).unwrap(), code);
+    }
+}